@@ -0,0 +1,168 @@
+use std::ops::Range;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Thread-safe allocator of sequential IDs.
+///
+/// IDs inside reserved ranges are never handed out, which lets callers keep
+/// IDs already used by imported data while allocating new ones. A single
+/// `IdGen` can be shared by reference across threads.
+///
+/// Allocations are returned as half-open ranges, so the largest ID ever
+/// handed out is `u64::MAX - 1`; `u64::MAX` itself is never allocated.
+#[derive(Debug)]
+pub struct IdGen {
+    next: AtomicU64,
+    reserved: Vec<Range<u64>>,
+}
+
+impl IdGen {
+    /// Creates a generator whose first ID is `start`.
+    pub fn new(start: u64) -> Self {
+        Self {
+            next: AtomicU64::new(start),
+            reserved: Vec::new(),
+        }
+    }
+
+    /// Excludes `range` from allocation.
+    pub fn with_reserved(mut self, range: Range<u64>) -> Self {
+        if !range.is_empty() {
+            self.reserved.push(range);
+            self.reserved.sort_by_key(|r| r.start);
+        }
+        self
+    }
+
+    /// Returns the next free ID.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the ID space is exhausted, i.e. the next free ID would be
+    /// `u64::MAX`.
+    pub fn next_id(&self) -> u64 {
+        self.reserve(1).start
+    }
+
+    /// Atomically allocates `len` consecutive IDs that do not overlap any
+    /// reserved range.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the block would have to include `u64::MAX`, which is never
+    /// allocated. The generator is left unchanged in that case, so IDs are
+    /// never handed out twice.
+    pub fn reserve(&self, len: u64) -> Range<u64> {
+        let mut current = self.next.load(Ordering::Relaxed);
+        loop {
+            let Some(start) = self.first_fit(current, len) else {
+                panic!("IdGen exhausted: cannot allocate {len} more IDs after {current}");
+            };
+            let end = start + len;
+            match self.next.compare_exchange_weak(
+                current,
+                end,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return start..end,
+                Err(actual) => current = actual,
+            }
+        }
+    }
+
+    /// Returns the value the next allocation will start searching from.
+    pub fn peek(&self) -> u64 {
+        self.next.load(Ordering::Relaxed)
+    }
+
+    /// Returns the first start at or after `start` whose block of `len` IDs
+    /// avoids the reserved ranges, or `None` if such a block would overflow.
+    fn first_fit(&self, mut start: u64, len: u64) -> Option<u64> {
+        for r in &self.reserved {
+            if start.checked_add(len)? <= r.start {
+                break;
+            }
+            if start < r.end {
+                start = r.end;
+            }
+        }
+        start.checked_add(len)?;
+        Some(start)
+    }
+}
+
+impl Default for IdGen {
+    /// Starts at 1, matching STEP instance numbering.
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use std::thread;
+
+    #[test]
+    fn allocates_sequentially() {
+        let ids = IdGen::default();
+        assert_eq!(ids.next_id(), 1);
+        assert_eq!(ids.next_id(), 2);
+        assert_eq!(ids.reserve(3), 3..6);
+        assert_eq!(ids.peek(), 6);
+    }
+
+    #[test]
+    fn skips_reserved_ranges() {
+        let ids = IdGen::new(1).with_reserved(5..8).with_reserved(2..4);
+        let got: Vec<u64> = (0..5).map(|_| ids.next_id()).collect();
+        assert_eq!(got, vec![1, 4, 8, 9, 10]);
+    }
+
+    #[test]
+    fn blocks_do_not_straddle_reserved_ranges() {
+        let ids = IdGen::new(1).with_reserved(4..6);
+        assert_eq!(ids.reserve(2), 1..3);
+        assert_eq!(ids.reserve(2), 6..8);
+    }
+
+    #[test]
+    fn allocates_up_to_u64_max_minus_one() {
+        let ids = IdGen::new(u64::MAX - 2);
+        assert_eq!(ids.next_id(), u64::MAX - 2);
+        assert_eq!(ids.next_id(), u64::MAX - 1);
+        assert_eq!(ids.peek(), u64::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "IdGen exhausted")]
+    fn never_allocates_u64_max() {
+        IdGen::new(u64::MAX).next_id();
+    }
+
+    #[test]
+    #[should_panic(expected = "IdGen exhausted")]
+    fn panics_when_reserved_range_reaches_the_end() {
+        let ids = IdGen::new(1).with_reserved(2..u64::MAX);
+        assert_eq!(ids.next_id(), 1);
+        ids.next_id();
+    }
+
+    #[test]
+    fn concurrent_allocation_is_unique() {
+        let ids = IdGen::default();
+        let all: Vec<u64> = thread::scope(|s| {
+            let handles: Vec<_> = (0..4)
+                .map(|_| s.spawn(|| (0..1000).map(|_| ids.next_id()).collect::<Vec<_>>()))
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|h| h.join().unwrap())
+                .collect()
+        });
+        let unique: HashSet<u64> = all.iter().copied().collect();
+        assert_eq!(unique.len(), 4000);
+        assert_eq!(ids.peek(), 4001);
+    }
+}
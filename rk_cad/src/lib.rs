@@ -1,3 +1,8 @@
+mod id_gen;
+pub mod step;
+
+pub use id_gen::IdGen;
+
 pub fn add(left: u64, right: u64) -> u64 {
     left + right
 }
@@ -0,0 +1,144 @@
+use super::lexer::{tokens, TokenKind};
+use super::split_records;
+
+/// Rewrites Part 21 text in a canonical layout.
+///
+/// Each record is written on its own line. Comments and all whitespace
+/// outside string literals are removed, and reals are rewritten in their
+/// shortest round-trip form (`1.000000` becomes `1.`, `0.00000012` becomes
+/// `1.2E-7`). Reals outside the normal `f64` range, such as `1.0E-400`, are
+/// kept as written. Strings, enumerations and references are copied
+/// verbatim, so the result describes the same data and two files that differ
+/// only in layout format identically.
+pub fn format_step(src: &str) -> String {
+    let mut out = String::with_capacity(src.len());
+    for record in split_records(src) {
+        let start = out.len();
+        format_record(record, &mut out);
+        if out.len() > start {
+            out.push_str(";\n");
+        }
+    }
+    out
+}
+
+fn format_record(record: &str, out: &mut String) {
+    for token in tokens(record).filter(|t| !t.is_trivia()) {
+        match token.kind {
+            TokenKind::Number => write_number(token.text, out),
+            _ => out.push_str(token.text),
+        }
+    }
+}
+
+fn write_number(token: &str, out: &mut String) {
+    if !token.contains('.') {
+        out.push_str(token);
+        return;
+    }
+    match token.parse::<f64>() {
+        Ok(value) if value.is_normal() || (value == 0.0 && !has_nonzero_digit(token)) => {
+            out.push_str(&format_real(value))
+        }
+        // Overflow, underflow to zero and subnormals lose precision as f64.
+        _ => out.push_str(token),
+    }
+}
+
+fn has_nonzero_digit(token: &str) -> bool {
+    token
+        .split(['E', 'e'])
+        .next()
+        .is_some_and(|mantissa| mantissa.bytes().any(|b| matches!(b, b'1'..=b'9')))
+}
+
+/// Formats `value` in the shortest Part 21 real form that parses back to it.
+fn format_real(value: f64) -> String {
+    let magnitude = value.abs();
+    if magnitude == 0.0 || (1e-6..1e15).contains(&magnitude) {
+        let mut s = value.to_string();
+        if !s.contains('.') {
+            s.push('.');
+        }
+        return s;
+    }
+    let s = format!("{:E}", value);
+    let (mantissa, exponent) = s.split_once('E').unwrap();
+    if mantissa.contains('.') {
+        format!("{mantissa}E{exponent}")
+    } else {
+        format!("{mantissa}.E{exponent}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_one_record_per_line() {
+        let src = "ISO-10303-21;HEADER;FILE_SCHEMA (('CONFIG_CONTROL_DESIGN'));ENDSEC;\n\
+                   DATA;#1 = CARTESIAN_POINT ( 'p 1', ( 0.0, 1.5, -2.000 ) ) ;\n\
+                   #2=VERTEX_POINT('',\n   #1);ENDSEC;END-ISO-10303-21;";
+        assert_eq!(
+            format_step(src),
+            "ISO-10303-21;\nHEADER;\nFILE_SCHEMA(('CONFIG_CONTROL_DESIGN'));\nENDSEC;\n\
+             DATA;\n#1=CARTESIAN_POINT('p 1',(0.,1.5,-2.));\n#2=VERTEX_POINT('',#1);\n\
+             ENDSEC;\nEND-ISO-10303-21;\n"
+        );
+    }
+
+    #[test]
+    fn normalizes_reals() {
+        assert_eq!(format_real(1.0), "1.");
+        assert_eq!(format_real(-0.25), "-0.25");
+        assert_eq!(format_real(0.0), "0.");
+        assert_eq!(format_real(1.2e-7), "1.2E-7");
+        assert_eq!(format_real(1e20), "1.E20");
+        assert_eq!(
+            format_step("#1=A(1.000000,1.E-7,+2.50E+01,.5,3);"),
+            "#1=A(1.,1.E-7,25.,0.5,3);\n"
+        );
+        assert_eq!(
+            format_step("#1=A(1.0E-400,-0.000E+5,5.0E-320,1.0E400);"),
+            "#1=A(1.0E-400,-0.,5.0E-320,1.0E400);\n"
+        );
+    }
+
+    #[test]
+    fn keeps_strings_enums_and_complex_instances() {
+        let src = "#1 = ( NAMED_UNIT ( * ) SI_UNIT ( .MILLI. , .METRE. ) );\
+                   #2=PRODUCT('it''s  1.000', 'a;b',\"0FF\",$);";
+        assert_eq!(
+            format_step(src),
+            "#1=(NAMED_UNIT(*)SI_UNIT(.MILLI.,.METRE.));\n\
+             #2=PRODUCT('it''s  1.000','a;b',\"0FF\",$);\n"
+        );
+    }
+
+    #[test]
+    fn normalizes_records_after_resource_references() {
+        let src = "REFERENCE;\n#11 = <bolt.stp#head> ;\nENDSEC;\nDATA;\n\
+                   #1 = CARTESIAN_POINT ( '', ( 0.0, 1.000, 2. ) );\nENDSEC;";
+        assert_eq!(
+            format_step(src),
+            "REFERENCE;\n#11=<bolt.stp#head>;\nENDSEC;\nDATA;\n\
+             #1=CARTESIAN_POINT('',(0.,1.,2.));\nENDSEC;\n"
+        );
+    }
+
+    #[test]
+    fn drops_comments() {
+        assert_eq!(
+            format_step("/* header */ DATA; /* only a comment */;\n#1=A(/*x*/1.0);"),
+            "DATA;\n#1=A(1.);\n"
+        );
+    }
+
+    #[test]
+    fn formatting_is_idempotent() {
+        let src = "DATA;#10=DIRECTION('',(0.,0.,1.0E0));#11 = (A() B(2.5E-9));ENDSEC;";
+        let once = format_step(src);
+        assert_eq!(format_step(&once), once);
+    }
+}
@@ -0,0 +1,283 @@
+//! Tokenizer shared by the Part 21 text helpers.
+
+/// Lexical category of a [`Token`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum TokenKind {
+    Whitespace,
+    /// A `/* ... */` comment.
+    Comment,
+    /// A `'...'` string literal, quotes included.
+    String,
+    /// A `"..."` binary literal, quotes included.
+    Binary,
+    /// A `.NAME.` enumeration value.
+    Enumeration,
+    /// A `<uri>` resource reference (Part 21 edition 3).
+    Resource,
+    /// A `#n` instance name.
+    Reference,
+    /// An integer or real.
+    Number,
+    /// An entity or section keyword, including user-defined `!` keywords.
+    Keyword,
+    /// Any other single character, such as `(`, `,`, `=` or `;`.
+    Punct,
+}
+
+/// A token borrowed from the source text, starting at byte offset `start`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct Token<'a> {
+    pub kind: TokenKind,
+    pub start: usize,
+    pub text: &'a str,
+}
+
+impl Token<'_> {
+    /// Returns `true` for tokens that carry no meaning (whitespace, comments).
+    pub fn is_trivia(&self) -> bool {
+        matches!(self.kind, TokenKind::Whitespace | TokenKind::Comment)
+    }
+
+    /// Returns `true` if this is the punctuation character `c`.
+    pub fn is_punct(&self, c: char) -> bool {
+        self.kind == TokenKind::Punct && self.text.starts_with(c)
+    }
+}
+
+/// Splits `src` into tokens. Unterminated comments and literals extend to
+/// the end of the input.
+pub(super) fn tokens(src: &str) -> Tokens<'_> {
+    Tokens { src, pos: 0 }
+}
+
+pub(super) struct Tokens<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        let bytes = self.src.as_bytes();
+        let i = self.pos;
+        let c = *bytes.get(i)?;
+        let next = bytes.get(i + 1).copied();
+        let (kind, end) = match c {
+            b' ' | b'\t' | b'\r' | b'\n' => (
+                TokenKind::Whitespace,
+                i + count_while(&bytes[i..], |b| b.is_ascii_whitespace()),
+            ),
+            b'/' if next == Some(b'*') => (
+                TokenKind::Comment,
+                self.src[i + 2..]
+                    .find("*/")
+                    .map_or(bytes.len(), |e| i + e + 4),
+            ),
+            b'\'' => (TokenKind::String, string_end(bytes, i)),
+            b'"' => (
+                TokenKind::Binary,
+                self.src[i + 1..]
+                    .find('"')
+                    .map_or(bytes.len(), |e| i + e + 2),
+            ),
+            b'.' if enumeration_end(bytes, i).is_some() => {
+                (TokenKind::Enumeration, enumeration_end(bytes, i).unwrap())
+            }
+            b'<' if resource_end(bytes, i).is_some() => {
+                (TokenKind::Resource, resource_end(bytes, i).unwrap())
+            }
+            b'#' => (
+                TokenKind::Reference,
+                i + 1 + count_while(&bytes[i + 1..], |b| b.is_ascii_digit()),
+            ),
+            b'0'..=b'9' | b'+' | b'-' | b'.'
+                if c.is_ascii_digit() || next.is_some_and(|n| n.is_ascii_digit() || n == b'.') =>
+            {
+                (TokenKind::Number, number_end(bytes, i).max(i + 1))
+            }
+            _ if c.is_ascii_alphabetic() || c == b'!' => (
+                TokenKind::Keyword,
+                i + 1
+                    + count_while(&bytes[i + 1..], |b| {
+                        b.is_ascii_alphanumeric() || b == b'_' || b == b'-'
+                    }),
+            ),
+            _ => (
+                TokenKind::Punct,
+                i + self.src[i..].chars().next().map_or(1, char::len_utf8),
+            ),
+        };
+        self.pos = end;
+        Some(Token {
+            kind,
+            start: i,
+            text: &self.src[i..end],
+        })
+    }
+}
+
+fn count_while(bytes: &[u8], pred: impl Fn(u8) -> bool) -> usize {
+    bytes.iter().take_while(|&&b| pred(b)).count()
+}
+
+/// Returns the index just past the enumeration `.NAME.` starting at `start`,
+/// or `None` if the text there is not a complete enumeration.
+fn enumeration_end(bytes: &[u8], start: usize) -> Option<usize> {
+    let first = *bytes.get(start + 1)?;
+    if !(first.is_ascii_alphabetic() || first == b'_') {
+        return None;
+    }
+    let end = start
+        + 2
+        + count_while(&bytes[start + 2..], |b| {
+            b.is_ascii_alphanumeric() || b == b'_'
+        });
+    (bytes.get(end) == Some(&b'.')).then_some(end + 1)
+}
+
+/// Returns the index just past the resource reference `<...>` starting at
+/// `start`, or `None` if no `>` follows before whitespace or a `;`.
+fn resource_end(bytes: &[u8], start: usize) -> Option<usize> {
+    let len = count_while(&bytes[start + 1..], |b| {
+        !(b == b'>' || b == b';' || b.is_ascii_whitespace())
+    });
+    let end = start + 1 + len;
+    (bytes.get(end) == Some(&b'>')).then_some(end + 1)
+}
+
+/// Returns the index just past the string literal starting at `start`.
+fn string_end(bytes: &[u8], start: usize) -> usize {
+    let mut i = start + 1;
+    while i < bytes.len() {
+        if bytes[i] == b'\'' {
+            if bytes.get(i + 1) == Some(&b'\'') {
+                i += 2;
+                continue;
+            }
+            return i + 1;
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+fn number_end(bytes: &[u8], start: usize) -> usize {
+    let mut i = start;
+    if matches!(bytes[i], b'+' | b'-') {
+        i += 1;
+    }
+    i += count_while(&bytes[i..], |b| b.is_ascii_digit());
+    if bytes.get(i) == Some(&b'.') {
+        i += 1;
+        i += count_while(&bytes[i..], |b| b.is_ascii_digit());
+        if matches!(bytes.get(i), Some(b'E' | b'e')) {
+            let mut j = i + 1;
+            if matches!(bytes.get(j), Some(b'+' | b'-')) {
+                j += 1;
+            }
+            let digits = count_while(&bytes[j..], |b| b.is_ascii_digit());
+            if digits > 0 {
+                i = j + digits;
+            }
+        }
+    }
+    i
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(src: &str) -> Vec<(TokenKind, &str)> {
+        tokens(src).map(|t| (t.kind, t.text)).collect()
+    }
+
+    #[test]
+    fn tokenizes_an_instance() {
+        use TokenKind::*;
+        assert_eq!(
+            kinds("#1 = A('x''y',.T.,-1.5E-3,2,$);"),
+            vec![
+                (Reference, "#1"),
+                (Whitespace, " "),
+                (Punct, "="),
+                (Whitespace, " "),
+                (Keyword, "A"),
+                (Punct, "("),
+                (String, "'x''y'"),
+                (Punct, ","),
+                (Enumeration, ".T."),
+                (Punct, ","),
+                (Number, "-1.5E-3"),
+                (Punct, ","),
+                (Number, "2"),
+                (Punct, ","),
+                (Punct, "$"),
+                (Punct, ")"),
+                (Punct, ";"),
+            ]
+        );
+    }
+
+    #[test]
+    fn comments_and_literals_hide_their_contents() {
+        use TokenKind::*;
+        assert_eq!(
+            kinds("/* it's; */'a;/*'\"0F\""),
+            vec![
+                (Comment, "/* it's; */"),
+                (String, "'a;/*'"),
+                (Binary, "\"0F\""),
+            ]
+        );
+    }
+
+    #[test]
+    fn enumerations_must_be_closed_immediately() {
+        use TokenKind::*;
+        assert_eq!(
+            kinds(".T.,.F_1."),
+            vec![(Enumeration, ".T."), (Punct, ","), (Enumeration, ".F_1.")]
+        );
+        assert_eq!(
+            kinds("(.T);B(1.5)"),
+            vec![
+                (Punct, "("),
+                (Punct, "."),
+                (Keyword, "T"),
+                (Punct, ")"),
+                (Punct, ";"),
+                (Keyword, "B"),
+                (Punct, "("),
+                (Number, "1.5"),
+                (Punct, ")"),
+            ]
+        );
+    }
+
+    #[test]
+    fn resource_references_are_single_tokens() {
+        use TokenKind::*;
+        assert_eq!(
+            kinds("#11=<bolt.stp#head>;\nENDSEC;"),
+            vec![
+                (Reference, "#11"),
+                (Punct, "="),
+                (Resource, "<bolt.stp#head>"),
+                (Punct, ";"),
+                (Whitespace, "\n"),
+                (Keyword, "ENDSEC"),
+                (Punct, ";"),
+            ]
+        );
+        assert_eq!(kinds("<a;b>")[0], (Punct, "<"));
+    }
+
+    #[test]
+    fn unterminated_tokens_run_to_the_end() {
+        assert_eq!(kinds("/* x").len(), 1);
+        assert_eq!(kinds("'abc").len(), 1);
+        assert_eq!(tokens("ab cd").last().unwrap().start, 3);
+    }
+}
@@ -0,0 +1,12 @@
+//! Helpers for reading and writing ISO 10303-21 (STEP) text.
+
+mod format;
+mod lexer;
+mod records;
+mod scan;
+mod string;
+
+pub use format::format_step;
+pub use records::split_records;
+pub use scan::scan_keywords;
+pub use string::encode_string;
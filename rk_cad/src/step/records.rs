@@ -0,0 +1,95 @@
+use super::lexer::tokens;
+
+/// Splits Part 21 text into records on `;` terminators.
+///
+/// Terminators inside string literals and `/* ... */` comments are ignored,
+/// so several instances on one physical line (minified files) and records
+/// spanning many lines are both handled. Each returned record is trimmed and
+/// excludes its `;`; trailing text without a terminator is returned as a
+/// final record if it is not blank.
+pub fn split_records(src: &str) -> Vec<&str> {
+    let mut records = Vec::new();
+    let mut start = 0;
+    for token in tokens(src).filter(|t| t.is_punct(';')) {
+        push_record(&mut records, &src[start..token.start]);
+        start = token.start + 1;
+    }
+    if start < src.len() {
+        push_record(&mut records, &src[start..]);
+    }
+    records
+}
+
+fn push_record<'a>(records: &mut Vec<&'a str>, record: &'a str) {
+    let record = record.trim();
+    if !record.is_empty() {
+        records.push(record);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_multiple_records_on_one_line() {
+        let src = "#1=CARTESIAN_POINT('',(0.,0.,0.));#2=DIRECTION('',(0.,0.,1.));";
+        assert_eq!(
+            split_records(src),
+            vec![
+                "#1=CARTESIAN_POINT('',(0.,0.,0.))",
+                "#2=DIRECTION('',(0.,0.,1.))",
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_records_spanning_lines() {
+        let src = "#1=PRODUCT('a',\n  'b','',(#2));\nENDSEC;";
+        assert_eq!(
+            split_records(src),
+            vec!["#1=PRODUCT('a',\n  'b','',(#2))", "ENDSEC"]
+        );
+    }
+
+    #[test]
+    fn ignores_semicolons_in_strings() {
+        let src = "#1=PRODUCT('a;b','it''s;','',());";
+        assert_eq!(split_records(src), vec!["#1=PRODUCT('a;b','it''s;','',())"]);
+    }
+
+    #[test]
+    fn ignores_semicolons_in_comments() {
+        let src = "DATA; /* a; b */ #1=VERTEX_POINT('',#2);";
+        assert_eq!(
+            split_records(src),
+            vec!["DATA", "/* a; b */ #1=VERTEX_POINT('',#2)"]
+        );
+    }
+
+    #[test]
+    fn terminates_after_resource_references() {
+        let src = "REFERENCE;\n#11=<bolt.stp#head>;\n#12=<http://a.b/c.stp>;\nENDSEC;\n\
+                   DATA;\n#1=CARTESIAN_POINT('',(0.,0.,0.));";
+        assert_eq!(
+            split_records(src),
+            vec![
+                "REFERENCE",
+                "#11=<bolt.stp#head>",
+                "#12=<http://a.b/c.stp>",
+                "ENDSEC",
+                "DATA",
+                "#1=CARTESIAN_POINT('',(0.,0.,0.))",
+            ]
+        );
+    }
+
+    #[test]
+    fn returns_unterminated_tail() {
+        assert_eq!(
+            split_records("ENDSEC;\n  END-ISO"),
+            vec!["ENDSEC", "END-ISO"]
+        );
+        assert!(split_records(" \n ").is_empty());
+    }
+}
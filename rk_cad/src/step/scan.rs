@@ -0,0 +1,117 @@
+use std::collections::BTreeMap;
+
+use super::lexer::{tokens, TokenKind};
+use super::split_records;
+
+/// Counts entity keywords used by instances in Part 21 text.
+///
+/// Only instance records (`#n = ...`) are considered, so header entities are
+/// not counted. Each partial entity of a complex instance (`#n = (A() B())`)
+/// counts once. This does no parameter parsing and is intended for quickly
+/// surveying which entity types a file relies on.
+pub fn scan_keywords(src: &str) -> BTreeMap<String, usize> {
+    let mut counts = BTreeMap::new();
+    for record in split_records(src) {
+        for keyword in instance_keywords(record) {
+            *counts.entry(keyword.to_string()).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+/// Returns the keyword of a simple instance, or the keywords of the partial
+/// entities of a complex instance. Other records yield nothing.
+fn instance_keywords(record: &str) -> Vec<&str> {
+    let mut tokens = tokens(record).filter(|t| !t.is_trivia());
+    let is_instance = tokens
+        .next()
+        .is_some_and(|t| t.kind == TokenKind::Reference)
+        && tokens.next().is_some_and(|t| t.is_punct('='));
+    if !is_instance {
+        return Vec::new();
+    }
+    match tokens.next() {
+        Some(t) if t.kind == TokenKind::Keyword => vec![t.text],
+        Some(t) if t.is_punct('(') => {
+            let mut keywords = Vec::new();
+            let mut depth = 1usize;
+            for t in tokens {
+                if t.is_punct('(') {
+                    depth += 1;
+                } else if t.is_punct(')') {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                } else if depth == 1 && t.kind == TokenKind::Keyword {
+                    keywords.push(t.text);
+                }
+            }
+            keywords
+        }
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_simple_instances() {
+        let src = "ISO-10303-21;\nHEADER;\nFILE_NAME('a','',(''),(''),'','','');\nENDSEC;\n\
+                   DATA;\n#1=CARTESIAN_POINT('',(0.,0.,0.));\n#2 = CARTESIAN_POINT('',(1.,0.,0.));\n\
+                   #3=LINE('',#1,#4);ENDSEC;\nEND-ISO-10303-21;";
+        let counts = scan_keywords(src);
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts["CARTESIAN_POINT"], 2);
+        assert_eq!(counts["LINE"], 1);
+    }
+
+    #[test]
+    fn counts_each_partial_entity_of_complex_instances() {
+        let src = "#1=( LENGTH_UNIT() NAMED_UNIT(*) SI_UNIT(.MILLI.,.METRE.) );\
+                   #2=(NAMED_UNIT(*)PLANE_ANGLE_UNIT()SI_UNIT($,.RADIAN.));";
+        let counts = scan_keywords(src);
+        assert_eq!(counts["NAMED_UNIT"], 2);
+        assert_eq!(counts["SI_UNIT"], 2);
+        assert_eq!(counts["LENGTH_UNIT"], 1);
+        assert_eq!(counts["PLANE_ANGLE_UNIT"], 1);
+        assert_eq!(counts.len(), 4);
+    }
+
+    #[test]
+    fn ignores_strings_and_comments() {
+        let src = "/* #9=FAKE(); */ #1=PRODUCT('X(Y)','#2=NOT_AN_ENTITY()','',(#3));\
+                   #4=(A('B C(')D());";
+        let counts = scan_keywords(src);
+        let keys: Vec<&str> = counts.keys().map(String::as_str).collect();
+        assert_eq!(keys, vec!["A", "D", "PRODUCT"]);
+    }
+
+    #[test]
+    fn ignores_comments_inside_instances() {
+        let src = "#1= /* c */ A();\
+                   #2=(B() /* C() */ D());\
+                   #3=(E() /* it's */ F('x'));";
+        let counts = scan_keywords(src);
+        let keys: Vec<&str> = counts.keys().map(String::as_str).collect();
+        assert_eq!(keys, vec!["A", "B", "D", "E", "F"]);
+    }
+
+    #[test]
+    fn counts_data_after_a_reference_section() {
+        let src = "REFERENCE;\n#11=<bolt.stp#head>;\nENDSEC;\nDATA;\n\
+                   #1=CARTESIAN_POINT('',(0.,0.,0.));\n#2=DIRECTION('',(0.,0.,1.));\n\
+                   #3=A(.T);#4=B(1.5);\nENDSEC;";
+        let counts = scan_keywords(src);
+        let keys: Vec<&str> = counts.keys().map(String::as_str).collect();
+        assert_eq!(keys, vec!["A", "B", "CARTESIAN_POINT", "DIRECTION"]);
+    }
+
+    #[test]
+    fn keeps_user_defined_keywords() {
+        let counts = scan_keywords("#1=!MY_ENTITY(1);");
+        assert_eq!(counts["!MY_ENTITY"], 1);
+    }
+}
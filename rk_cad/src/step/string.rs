@@ -0,0 +1,84 @@
+use std::fmt::Write;
+
+/// Encodes `s` as a STEP `STRING` parameter, including the enclosing quotes.
+///
+/// Apostrophes and backslashes are doubled, control characters use the
+/// `\X\hh` form and runs of non-ASCII characters are written with the
+/// `\X2\...\X0\` (or `\X4\...\X0\` outside the BMP) control directives, so
+/// the result is always a valid Part 21 basic-alphabet literal.
+pub fn encode_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('\'');
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => out.push_str("''"),
+            '\\' => out.push_str("\\\\"),
+            ' '..='~' => out.push(c),
+            '\0'..='\u{1f}' | '\u{7f}' => {
+                write!(out, "\\X\\{:02X}", c as u32).unwrap();
+            }
+            _ => {
+                let mut run = vec![c];
+                while let Some(&next) = chars.peek() {
+                    if next.is_ascii() {
+                        break;
+                    }
+                    run.push(next);
+                    chars.next();
+                }
+                write_extended(&mut out, &run);
+            }
+        }
+    }
+    out.push('\'');
+    out
+}
+
+fn write_extended(out: &mut String, run: &[char]) {
+    if run.iter().all(|&c| (c as u32) <= 0xFFFF) {
+        out.push_str("\\X2\\");
+        for &c in run {
+            write!(out, "{:04X}", c as u32).unwrap();
+        }
+    } else {
+        out.push_str("\\X4\\");
+        for &c in run {
+            write!(out, "{:08X}", c as u32).unwrap();
+        }
+    }
+    out.push_str("\\X0\\");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_ascii_is_quoted() {
+        assert_eq!(encode_string("Part"), "'Part'");
+        assert_eq!(encode_string(""), "''");
+    }
+
+    #[test]
+    fn quotes_and_backslashes_are_doubled() {
+        assert_eq!(encode_string("it's"), "'it''s'");
+        assert_eq!(encode_string(r"a\b"), r"'a\\b'");
+    }
+
+    #[test]
+    fn control_characters_use_x_directive() {
+        assert_eq!(encode_string("a\nb"), r"'a\X\0Ab'");
+    }
+
+    #[test]
+    fn non_ascii_runs_use_x2_directive() {
+        assert_eq!(encode_string("Größe"), r"'Gr\X2\00F600DF\X0\e'");
+        assert_eq!(encode_string("部品"), r"'\X2\90E854C1\X0\'");
+    }
+
+    #[test]
+    fn astral_characters_use_x4_directive() {
+        assert_eq!(encode_string("a😀"), r"'a\X4\0001F600\X0\'");
+    }
+}